@@ -84,6 +84,22 @@ register_bitfields![u32,
 const GPIO0_BASE: StaticRef<GpioRegisters> =
     unsafe { StaticRef::new(0x1001_2000 as *const GpioRegisters) };
 
+/// Which level-triggered condition to watch for with
+/// `GpioPin::enable_level_interrupt`.
+pub enum Level {
+    High,
+    Low,
+    Both,
+}
+
+/// Selects between the two HW I/O Function banks a pin can be routed to
+/// when it is handed off to an on-chip peripheral (UART, SPI, PWM, ...) via
+/// `GpioPin::set_alternate_function`.
+pub enum IofFunction {
+    Iof0,
+    Iof1,
+}
+
 pub struct Port {
     pins: [GpioPin; 32],
 }
@@ -163,6 +179,45 @@ impl GpioPin {
     pub fn set_client<C: hil::gpio::Client>(&self, client: &'static C) {
         self.client.set(client);
     }
+
+    /// Routes this pin to a hardware peripheral's HW I/O Function instead of
+    /// plain GPIO, selecting IOF0 or IOF1 as the function source. Peripheral
+    /// drivers (UART, SPI, ...) should call this as part of pin
+    /// configuration instead of writing `iof_en`/`iof_sel` directly.
+    pub fn set_alternate_function(&self, iof: IofFunction) {
+        match iof {
+            IofFunction::Iof0 => self.registers.iof_sel.modify(self.clear),
+            IofFunction::Iof1 => self.registers.iof_sel.modify(self.set),
+        }
+        self.registers.iof_en.modify(self.set);
+    }
+
+    /// Returns the pin to plain GPIO control, undoing
+    /// `set_alternate_function`.
+    pub fn disable_alternate_function(&self) {
+        self.registers.iof_en.modify(self.clear);
+    }
+
+    /// Selects the pin's output drive strength: `true` for high drive,
+    /// `false` for the default/low drive.
+    pub fn set_drive_strength(&self, high: bool) {
+        if high {
+            self.registers.drive.modify(self.set);
+        } else {
+            self.registers.drive.modify(self.clear);
+        }
+    }
+
+    /// Has the hardware invert the pin's output logic level, so e.g. an
+    /// active-low LED can be driven with the same polarity as the rest of
+    /// the application without extra CPU cost.
+    pub fn set_output_invert(&self, invert: bool) {
+        if invert {
+            self.registers.out_xor.modify(self.set);
+        } else {
+            self.registers.out_xor.modify(self.clear);
+        }
+    }
 }
 
 impl hil::gpio::PinCtl for GpioPin {
@@ -268,18 +323,94 @@ impl hil::gpio::Pin for GpioPin {
     }
 
     fn enable_interrupt(&self, client_data: usize, mode: hil::gpio::InterruptMode) {
-        // let mode_bits = match mode {
-        //     hil::gpio::InterruptMode::EitherEdge => 0b00,
-        //     hil::gpio::InterruptMode::RisingEdge => 0b01,
-        //     hil::gpio::InterruptMode::FallingEdge => 0b10,
-        // };
-        // self.client_data.set(client_data);
-        // GPIOPin::set_interrupt_mode(self, mode_bits);
-        // GPIOPin::enable_interrupt(self);
+        self.client_data.set(client_data);
+        match mode {
+            hil::gpio::InterruptMode::RisingEdge => {
+                self.registers.rise_ie.modify(self.set);
+            }
+            hil::gpio::InterruptMode::FallingEdge => {
+                self.registers.fall_ie.modify(self.set);
+            }
+            hil::gpio::InterruptMode::EitherEdge => {
+                self.registers.rise_ie.modify(self.set);
+                self.registers.fall_ie.modify(self.set);
+            }
+        }
     }
 
     fn disable_interrupt(&self) {
-        // GPIOPin::disable_interrupt(self);
+        self.registers.rise_ie.modify(self.clear);
+        self.registers.fall_ie.modify(self.clear);
+        // Clear any stale pending bits so a disabled pin can't leave an
+        // edge latched for the next time it's re-enabled.
+        self.registers.rise_ip.write(self.set);
+        self.registers.fall_ip.write(self.set);
+    }
+}
+
+impl GpioPin {
+    /// Arms the level-triggered (as opposed to edge-triggered) interrupt for
+    /// this pin. Unlike `enable_interrupt`, level interrupts re-assert for as
+    /// long as the condition holds, so `handle_interrupt` masks the enable
+    /// bit once it fires and the client must call `rearm_level_interrupt` to
+    /// re-arm it.
+    pub fn enable_level_interrupt(&self, client_data: usize, level: Level) {
+        self.client_data.set(client_data);
+        match level {
+            Level::High => self.registers.high_ie.modify(self.set),
+            Level::Low => self.registers.low_ie.modify(self.set),
+            Level::Both => {
+                self.registers.high_ie.modify(self.set);
+                self.registers.low_ie.modify(self.set);
+            }
+        }
+    }
+
+    /// Re-arms a level interrupt previously masked by `handle_interrupt`.
+    pub fn rearm_level_interrupt(&self, level: Level) {
+        match level {
+            Level::High => self.registers.high_ie.modify(self.set),
+            Level::Low => self.registers.low_ie.modify(self.set),
+            Level::Both => {
+                self.registers.high_ie.modify(self.set);
+                self.registers.low_ie.modify(self.set);
+            }
+        }
+    }
+
+    /// Services a pending interrupt for this pin: acknowledges it (the
+    /// `*_ip` registers are write-1-to-clear) and notifies the client.
+    fn handle_interrupt(&self) {
+        if self.registers.rise_ip.is_set(self.pin) {
+            self.registers.rise_ip.write(self.set);
+            self.client.map(|c| c.fired(self.client_data.get()));
+        }
+        if self.registers.fall_ip.is_set(self.pin) {
+            self.registers.fall_ip.write(self.set);
+            self.client.map(|c| c.fired(self.client_data.get()));
+        }
+        if self.registers.high_ip.is_set(self.pin) {
+            self.registers.high_ip.write(self.set);
+            // Level interrupts re-assert while the condition holds, so mask
+            // the enable bit to avoid an interrupt storm until the client
+            // explicitly re-arms it via `rearm_level_interrupt`.
+            self.registers.high_ie.modify(self.clear);
+            self.client.map(|c| c.fired(self.client_data.get()));
+        }
+        if self.registers.low_ip.is_set(self.pin) {
+            self.registers.low_ip.write(self.set);
+            self.registers.low_ie.modify(self.clear);
+            self.client.map(|c| c.fired(self.client_data.get()));
+        }
+    }
+}
+
+impl Port {
+    /// Called from the PLIC dispatch code: FE310 wires each GPIO pin to its
+    /// own interrupt source, so the PLIC handler identifies the pin and
+    /// hands it off here.
+    pub fn handle_interrupt(&self, pin_index: usize) {
+        self.pins[pin_index].handle_interrupt();
     }
 }
 